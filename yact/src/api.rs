@@ -1,23 +1,72 @@
 // src/lib.rs  或  src/main.rs 根据需要
 use anyhow::{Context, Result};
+use futures_util::{Stream, StreamExt};
 use reqwest::Client;
-use serde_json::{Value, json};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::time::Duration;
 
+use crate::model::{Configs, ProxiesResponse, ProxyNode, Rule, RulesResponse};
+use crate::settings::ClashtuiConfig;
+
+/// Retry/timeout/redirect policy for a `MihomoClient`'s request layer.
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    pub max_retries: u32,
+    pub base_backoff: Duration,
+    pub follow_redirects: bool,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(15),
+            max_retries: 3,
+            base_backoff: Duration::from_millis(200),
+            follow_redirects: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MihomoClient {
     base_url: String,
     secret: String,
     client: Client,
+    options: ClientOptions,
 }
 
 impl MihomoClient {
-    /// 创建客户端实例
+    /// 创建客户端实例（使用默认的重试/超时策略）
     /// 示例: MihomoClient::new("http://127.0.0.1:9090", Some("your-secret-key"))
     pub fn new(base_url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self::with_options(base_url, secret, ClientOptions::default())
+    }
+
+    /// 创建客户端实例，并自定义重试/超时/重定向策略
+    pub fn with_options(
+        base_url: impl Into<String>,
+        secret: impl Into<String>,
+        options: ClientOptions,
+    ) -> Self {
+        let redirect_policy = if options.follow_redirects {
+            reqwest::redirect::Policy::default()
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+
+        // Only bound connect time at the client level. A whole-request
+        // `.timeout()` here would also bound how long a response body can be
+        // read for, which would kill every long-lived `*_stream()` endpoint
+        // (logs/traffic/memory/connections) partway through. `request_timeout`
+        // is instead applied per-call in `send_with_retry`, only for
+        // non-streaming calls.
         let client = Client::builder()
-            .timeout(Duration::from_secs(15))
+            .connect_timeout(options.connect_timeout)
+            .redirect(redirect_policy)
             .build()
             .expect("Failed to build reqwest client");
 
@@ -25,6 +74,48 @@ impl MihomoClient {
             base_url: base_url.into().trim_end_matches('/').to_string(),
             secret: secret.into().to_string(),
             client,
+            options,
+        }
+    }
+
+    /// Construct a client from a named profile in a loaded `ClashtuiConfig`,
+    /// or its `default_profile` when `name` is `None`. If the profile carries
+    /// a `spawn` directive, the core process is launched first and this waits
+    /// for the controller to come up before returning.
+    pub async fn from_profile(config: &ClashtuiConfig, name: Option<&str>) -> Result<Self> {
+        let profile = config.profile(name)?;
+        let client = Self::new(profile.base_url.clone(), profile.secret.clone());
+
+        if let Some(spawn_conf) = &profile.spawn {
+            spawn_conf.spawn().context("failed to spawn core process")?;
+            client
+                .wait_until_reachable(Duration::from_secs(10))
+                .await
+                .context("core process did not come up")?;
+        }
+
+        Ok(client)
+    }
+
+    /// Poll `GET /version` until the controller responds or `timeout`
+    /// elapses. Used right after spawning a fresh core process, which may
+    /// still be starting up when `from_profile` wants to hand back a usable
+    /// client.
+    async fn wait_until_reachable(&self, timeout: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let poll_interval = Duration::from_millis(200);
+
+        loop {
+            if self.get_version().await.is_ok() {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(anyhow::anyhow!(
+                    "core process did not become reachable within {:?}",
+                    timeout
+                ));
+            }
+            tokio::time::sleep(poll_interval).await;
         }
     }
 
@@ -37,21 +128,104 @@ impl MihomoClient {
         req
     }
 
+    /// Send `req`, retrying on connection errors and 5xx/429 responses up to
+    /// `options.max_retries` with exponential backoff, honoring a
+    /// `Retry-After` header when present. Non-idempotent requests (`idempotent
+    /// = false`) are only retried on pre-response transport errors, since a
+    /// 5xx/429 there may mean the write already landed.
+    ///
+    /// `streaming` must be `true` for endpoints whose response body is an
+    /// open-ended stream (SSE logs, newline-delimited metrics): those calls
+    /// skip `request_timeout` entirely, since the whole point is a read that
+    /// never completes. Non-streaming calls are bounded by `request_timeout`
+    /// per attempt.
+    async fn send_with_retry(
+        &self,
+        req: reqwest::RequestBuilder,
+        idempotent: bool,
+        streaming: bool,
+    ) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+
+        loop {
+            let attempt_req = req
+                .try_clone()
+                .context("request cannot be retried (streaming body)")?;
+
+            let outcome = if streaming {
+                attempt_req.send().await.map_err(Some)
+            } else {
+                match tokio::time::timeout(self.options.request_timeout, attempt_req.send()).await {
+                    Ok(result) => result.map_err(Some),
+                    Err(_) => Err(None),
+                }
+            };
+
+            match outcome {
+                Ok(resp) => {
+                    let status = resp.status();
+                    let retryable = status.is_server_error()
+                        || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+
+                    if !retryable || !idempotent || attempt >= self.options.max_retries {
+                        return resp.error_for_status().map_err(Into::into);
+                    }
+
+                    let delay = retry_after_delay(&resp)
+                        .unwrap_or_else(|| backoff_delay(self.options.base_backoff, attempt));
+                    attempt += 1;
+                    tracing::warn!(
+                        "request to {} returned {}, retrying ({}/{})",
+                        resp.url(),
+                        status,
+                        attempt,
+                        self.options.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(maybe_err) => {
+                    let message = match &maybe_err {
+                        Some(e) => e.to_string(),
+                        None => {
+                            format!("request timed out after {:?}", self.options.request_timeout)
+                        }
+                    };
+
+                    if attempt >= self.options.max_retries {
+                        return match maybe_err {
+                            Some(e) => Err(e.into()),
+                            None => Err(anyhow::anyhow!(message)),
+                        };
+                    }
+
+                    let delay = backoff_delay(self.options.base_backoff, attempt);
+                    attempt += 1;
+                    tracing::warn!(
+                        "request error: {}, retrying ({}/{})",
+                        message,
+                        attempt,
+                        self.options.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
     // ──────────────────────────────────────────────────────────────
     // 配置相关
     // ──────────────────────────────────────────────────────────────
 
     /// GET /configs
     /// 获取当前配置信息
-    pub async fn get_configs(&self) -> Result<Value> {
+    pub async fn get_configs(&self) -> Result<Configs> {
         let resp = self
-            .request(reqwest::Method::GET, "/configs")
-            .send()
+            .send_with_retry(self.request(reqwest::Method::GET, "/configs"), true, false)
             .await?
-            .error_for_status()?
-            .json::<Value>()
-            .await?;
-        // println!("{:?}", resp);
+            .json::<Configs>()
+            .await
+            .context("invalid configs response")?;
+
         Ok(resp)
     }
 
@@ -83,7 +257,9 @@ impl MihomoClient {
             req = req.json(&body);
         }
 
-        let _ = req.send().await?.error_for_status()?;
+        // PUT /configs is not idempotent (it may trigger an in-progress
+        // reload), so only retry pre-response transport errors.
+        let _ = self.send_with_retry(req, false, false).await?;
 
         Ok(())
     }
@@ -94,29 +270,27 @@ impl MihomoClient {
 
     /// GET /proxies
     /// 获取所有代理节点及分组信息
-    pub async fn get_proxies(&self) -> Result<Value> {
+    pub async fn get_proxies(&self) -> Result<HashMap<String, ProxyNode>> {
         let resp = self
-            .request(reqwest::Method::GET, "/proxies")
-            .send()
+            .send_with_retry(self.request(reqwest::Method::GET, "/proxies"), true, false)
             .await?
-            .error_for_status()?
-            .json::<Value>()
-            .await?;
+            .json::<ProxiesResponse>()
+            .await
+            .context("invalid proxies response")?;
 
-        Ok(resp)
+        Ok(resp.proxies)
     }
 
     /// GET /proxies/:name
     /// 获取单个代理/分组信息
-    pub async fn get_proxy(&self, name: &str) -> Result<Value> {
+    pub async fn get_proxy(&self, name: &str) -> Result<ProxyNode> {
         let path = format!("/proxies/{}", urlencoding::encode(name));
         let resp = self
-            .request(reqwest::Method::GET, &path)
-            .send()
+            .send_with_retry(self.request(reqwest::Method::GET, &path), true, false)
             .await?
-            .error_for_status()?
-            .json::<Value>()
-            .await?;
+            .json::<ProxyNode>()
+            .await
+            .context("invalid proxy response")?;
 
         Ok(resp)
     }
@@ -130,12 +304,11 @@ impl MihomoClient {
             "name": target_proxy
         });
 
-        let _ = self
-            .request(reqwest::Method::PUT, &path)
-            .json(&body)
-            .send()
-            .await?
-            .error_for_status()?;
+        let req = self.request(reqwest::Method::PUT, &path).json(&body);
+
+        // Switching a group's active proxy isn't idempotent from the
+        // server's point of view, so only retry transport-level failures.
+        let _ = self.send_with_retry(req, false, false).await?;
 
         Ok(())
     }
@@ -153,10 +326,12 @@ impl MihomoClient {
             timeout
         );
         let resp = self
-            .request(reqwest::Method::GET, &url_with_params)
-            .send()
+            .send_with_retry(
+                self.request(reqwest::Method::GET, &url_with_params),
+                true,
+                false,
+            )
             .await?
-            .error_for_status()?
             .json::<HashMap<String, u64>>()
             .await?;
 
@@ -173,16 +348,15 @@ impl MihomoClient {
     // ──────────────────────────────────────────────────────────────
 
     /// GET /rules
-    pub async fn get_rules(&self) -> Result<Value> {
+    pub async fn get_rules(&self) -> Result<Vec<Rule>> {
         let resp = self
-            .request(reqwest::Method::GET, "/rules")
-            .send()
+            .send_with_retry(self.request(reqwest::Method::GET, "/rules"), true, false)
             .await?
-            .error_for_status()?
-            .json::<Value>()
-            .await?;
+            .json::<RulesResponse>()
+            .await
+            .context("invalid rules response")?;
 
-        Ok(resp)
+        Ok(resp.rules)
     }
 
     // ──────────────────────────────────────────────────────────────
@@ -192,10 +366,8 @@ impl MihomoClient {
     /// GET /version
     pub async fn get_version(&self) -> Result<String> {
         let resp = self
-            .request(reqwest::Method::GET, "/version")
-            .send()
+            .send_with_retry(self.request(reqwest::Method::GET, "/version"), true, false)
             .await?
-            .error_for_status()?
             .text()
             .await?;
 
@@ -212,18 +384,386 @@ impl MihomoClient {
             path = format!("{}?level={}", path, urlencoding::encode(lv));
         }
 
-        let mut req = self.request(reqwest::Method::GET, &path);
+        let resp = self
+            .send_with_retry(self.request(reqwest::Method::GET, &path), true, true)
+            .await?;
+
+        Ok(resp)
+    }
+
+    /// GET /logs, decoded into a stream of typed events instead of a raw
+    /// `Response`. Handles SSE framing (`\n\n` event boundaries, multi-line
+    /// `data:` fields, `:`-prefixed comments) and buffers partial frames
+    /// across chunk boundaries, so callers get backpressure-aware log lines
+    /// without hand-rolling the SSE decoder themselves.
+    pub async fn log_stream(
+        &self,
+        level: Option<&str>,
+    ) -> Result<impl Stream<Item = Result<LogEntry>>> {
+        let resp = self.get_logs(level).await?;
+        let byte_stream = resp.bytes_stream();
+
+        Ok(async_stream::try_stream! {
+            futures_util::pin_mut!(byte_stream);
+            let mut buffer = String::new();
+
+            while let Some(chunk) = byte_stream.next().await {
+                let chunk = chunk.context("error reading log stream chunk")?;
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                while let Some(pos) = buffer.find("\n\n") {
+                    let frame: String = buffer.drain(..pos + 2).collect();
+                    let mut data_lines = Vec::new();
+                    for line in frame.lines() {
+                        if line.is_empty() || line.starts_with(':') {
+                            continue;
+                        }
+                        if let Some(data) = line.strip_prefix("data:") {
+                            data_lines.push(data.strip_prefix(' ').unwrap_or(data));
+                        }
+                    }
+                    if data_lines.is_empty() {
+                        continue;
+                    }
+
+                    let payload = data_lines.join("\n");
+                    let entry: LogEntry = serde_json::from_str(&payload)
+                        .with_context(|| format!("invalid log event payload: {}", payload))?;
+                    yield entry;
+                }
+            }
+        })
+    }
+
+    // ──────────────────────────────────────────────────────────────
+    // 实时指标（流式）
+    // ──────────────────────────────────────────────────────────────
+
+    /// GET /traffic, a newline-delimited JSON stream of throughput samples.
+    pub async fn traffic_stream(&self) -> Result<impl Stream<Item = Result<Traffic>>> {
+        let resp = self
+            .send_with_retry(self.request(reqwest::Method::GET, "/traffic"), true, true)
+            .await?;
+
+        Ok(ndjson_stream(resp))
+    }
+
+    /// GET /memory, a newline-delimited JSON stream of memory usage samples.
+    pub async fn memory_stream(&self) -> Result<impl Stream<Item = Result<Memory>>> {
+        let resp = self
+            .send_with_retry(self.request(reqwest::Method::GET, "/memory"), true, true)
+            .await?;
 
-        let resp = req.send().await?.error_for_status()?;
+        Ok(ndjson_stream(resp))
+    }
+
+    /// GET /connections, a newline-delimited JSON stream of connection
+    /// snapshots.
+    pub async fn connections_stream(
+        &self,
+    ) -> Result<impl Stream<Item = Result<ConnectionsSnapshot>>> {
+        let resp = self
+            .send_with_retry(
+                self.request(reqwest::Method::GET, "/connections"),
+                true,
+                true,
+            )
+            .await?;
+
+        Ok(ndjson_stream(resp))
+    }
+
+    // ──────────────────────────────────────────────────────────────
+    // 连接管理相关
+    // ──────────────────────────────────────────────────────────────
+
+    /// GET /connections, a one-shot snapshot (as opposed to `connections_stream`).
+    pub async fn get_connections(&self) -> Result<ConnectionsSnapshot> {
+        let resp = self
+            .send_with_retry(
+                self.request(reqwest::Method::GET, "/connections"),
+                true,
+                false,
+            )
+            .await?
+            .json::<ConnectionsSnapshot>()
+            .await
+            .context("invalid connections snapshot")?;
 
         Ok(resp)
     }
+
+    /// DELETE /connections, closes every active connection.
+    pub async fn close_all_connections(&self) -> Result<()> {
+        let _ = self
+            .send_with_retry(
+                self.request(reqwest::Method::DELETE, "/connections"),
+                true,
+                false,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// DELETE /connections/:id, closes a single connection by id.
+    pub async fn close_connection(&self, id: &str) -> Result<()> {
+        let path = format!("/connections/{}", urlencoding::encode(id));
+
+        let _ = self
+            .send_with_retry(self.request(reqwest::Method::DELETE, &path), true, false)
+            .await?;
+
+        Ok(())
+    }
+
+    // ──────────────────────────────────────────────────────────────
+    // 分组并发测速相关
+    // ──────────────────────────────────────────────────────────────
+
+    /// Test delay for every member of `group` concurrently, bounding
+    /// in-flight requests to `concurrency`. Tries Mihomo's native
+    /// `GET /group/:name/delay` first (which tests every member
+    /// server-side in one round trip); falls back to driving
+    /// `test_proxy_delay` per member when that endpoint isn't available.
+    pub async fn test_group_delay(
+        &self,
+        group: &str,
+        url: &str,
+        timeout_ms: u64,
+        concurrency: usize,
+    ) -> Result<HashMap<String, DelayResult>> {
+        if let Ok(native) = self.group_delay_native(group, url, timeout_ms).await {
+            return Ok(native);
+        }
+
+        let members = self
+            .get_proxy(group)
+            .await?
+            .all
+            .context("group has no members")?;
+
+        let concurrency = concurrency.max(1);
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(concurrency));
+        let results = futures_util::stream::iter(members.into_iter().map(|name| {
+            let semaphore = semaphore.clone();
+            async move {
+                let _permit = semaphore.acquire().await.expect("semaphore closed");
+                let result = match tokio::time::timeout(
+                    Duration::from_millis(timeout_ms),
+                    self.test_proxy_delay(&name, url, timeout_ms),
+                )
+                .await
+                {
+                    Ok(Ok(delay)) => DelayResult::Delay(delay),
+                    Ok(Err(e)) => DelayResult::Error(e.to_string()),
+                    Err(_) => DelayResult::TimedOut,
+                };
+                (name, result)
+            }
+        }))
+        .buffer_unordered(concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+        Ok(results.into_iter().collect())
+    }
+
+    /// GET /group/:name/delay, a single request that tests every member of
+    /// `group` server-side. Not available on all Mihomo versions.
+    async fn group_delay_native(
+        &self,
+        group: &str,
+        url: &str,
+        timeout_ms: u64,
+    ) -> Result<HashMap<String, DelayResult>> {
+        let path = format!("/group/{}/delay", urlencoding::encode(group));
+        let url_with_params = format!(
+            "{}?url={}&timeout={}",
+            path,
+            urlencoding::encode(url),
+            timeout_ms
+        );
+
+        let resp = self
+            .send_with_retry(
+                self.request(reqwest::Method::GET, &url_with_params),
+                true,
+                false,
+            )
+            .await?
+            .json::<HashMap<String, u64>>()
+            .await
+            .context("invalid group delay response")?;
+
+        Ok(resp
+            .into_iter()
+            .map(|(name, delay)| {
+                let result = if delay > 0 {
+                    DelayResult::Delay(delay)
+                } else {
+                    DelayResult::TimedOut
+                };
+                (name, result)
+            })
+            .collect())
+    }
+}
+
+/// Outcome of testing a single group member's delay during
+/// `test_group_delay`, distinguishing a real measurement from a dead node so
+/// one timeout doesn't abort the whole batch.
+#[derive(Debug, Clone)]
+pub enum DelayResult {
+    Delay(u64),
+    TimedOut,
+    Error(String),
+}
+
+/// Exponential backoff for retry attempt `attempt` (0-indexed): `base * 2^attempt`.
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1u32 << attempt.min(16))
+}
+
+/// Parse a `Retry-After` header (seconds form) off a response, if present.
+fn retry_after_delay(resp: &reqwest::Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Decode a newline-delimited JSON response body into a stream of typed
+/// items, buffering partial lines across chunk boundaries. Shared by every
+/// `*_stream()` endpoint that frames its output this way.
+fn ndjson_stream<T>(resp: reqwest::Response) -> impl Stream<Item = Result<T>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let byte_stream = resp.bytes_stream();
+
+    async_stream::try_stream! {
+        futures_util::pin_mut!(byte_stream);
+        let mut buffer = String::new();
+
+        while let Some(chunk) = byte_stream.next().await {
+            let chunk = chunk.context("error reading stream chunk")?;
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+            while let Some(pos) = buffer.find('\n') {
+                let line: String = buffer.drain(..=pos).collect();
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let item: T = serde_json::from_str(line)
+                    .with_context(|| format!("invalid line: {}", line))?;
+                yield item;
+            }
+        }
+
+        // The source may end without a trailing newline (a connection closed
+        // mid-sample, or a body like `/connections` that's a single JSON
+        // object with no internal `\n` at all) — flush whatever's left
+        // instead of silently dropping it.
+        let remainder = buffer.trim();
+        if !remainder.is_empty() {
+            let item: T = serde_json::from_str(remainder)
+                .with_context(|| format!("invalid line: {}", remainder))?;
+            yield item;
+        }
+    }
+}
+
+/// A single decoded Mihomo log event from `GET /logs`.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct LogEntry {
+    #[serde(rename = "type")]
+    pub log_type: String,
+    pub payload: String,
+}
+
+/// A single `GET /traffic` sample, bytes per second.
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+pub struct Traffic {
+    pub up: u64,
+    pub down: u64,
+}
+
+/// A single `GET /memory` sample, in bytes.
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+pub struct Memory {
+    pub inuse: u64,
+    pub oslimit: u64,
+}
+
+/// A `GET /connections` snapshot.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ConnectionsSnapshot {
+    #[serde(default)]
+    pub connections: Vec<Connection>,
+    #[serde(rename = "downloadTotal", default)]
+    pub download_total: u64,
+    #[serde(rename = "uploadTotal", default)]
+    pub upload_total: u64,
+    #[serde(default)]
+    pub memory: u64,
+}
+
+/// A single active connection as reported by Mihomo.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Connection {
+    pub id: String,
+    pub metadata: Value,
+    pub upload: u64,
+    pub download: u64,
+    pub start: String,
+    pub chains: Vec<String>,
+    pub rule: String,
+    #[serde(rename = "rulePayload", default)]
+    pub rule_payload: String,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+
+    /// Build a streaming `reqwest::Response` out of raw byte chunks, with no
+    /// network involved, so `ndjson_stream`'s framing can be exercised directly.
+    fn response_from_chunks(chunks: Vec<&'static str>) -> reqwest::Response {
+        let body = reqwest::Body::wrap_stream(futures_util::stream::iter(
+            chunks.into_iter().map(Ok::<_, std::io::Error>),
+        ));
+        http::Response::new(body).into()
+    }
+
+    #[derive(Debug, Clone, serde::Deserialize, PartialEq)]
+    struct Sample {
+        value: u32,
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_stream_flushes_trailing_unterminated_line() {
+        let resp = response_from_chunks(vec!["{\"value\":1}"]);
+        let stream = ndjson_stream::<Sample>(resp);
+        futures_util::pin_mut!(stream);
+
+        let items: Vec<Sample> = stream.map(|r| r.unwrap()).collect().await;
+        assert_eq!(items, vec![Sample { value: 1 }]);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_stream_splits_lines_across_chunk_boundaries() {
+        let resp = response_from_chunks(vec!["{\"value\":1}\n{\"va", "lue\":2}\n"]);
+        let stream = ndjson_stream::<Sample>(resp);
+        futures_util::pin_mut!(stream);
+
+        let items: Vec<Sample> = stream.map(|r| r.unwrap()).collect().await;
+        assert_eq!(items, vec![Sample { value: 1 }, Sample { value: 2 }]);
+    }
 
     #[tokio::test]
     async fn test_get_configs() {
@@ -231,18 +771,13 @@ mod tests {
 
         match client.get_configs().await {
             Ok(configs) => {
-                // Verify configs is not null and has some expected fields
-                assert_ne!(configs, json!(null), "configs should not be null");
-
-                // Check for common config fields
-                if let Some(obj) = configs.as_object() {
-                    // configs should have mixed-port or port field
-                    let has_port = obj.contains_key("mixed-port") || obj.contains_key("port");
-                    assert!(
-                        has_port || !obj.is_empty(),
-                        "configs should have port fields or other content"
-                    );
-                }
+                // Configs should expose at least one known port field or
+                // carry something in `extra` if the schema has shifted.
+                let has_port = configs.mixed_port.is_some() || configs.port.is_some();
+                assert!(
+                    has_port || !configs.extra.is_empty(),
+                    "configs should have port fields or other content"
+                );
             }
             Err(e) => {
                 eprintln!(
@@ -259,14 +794,8 @@ mod tests {
 
         match client.get_proxies().await {
             Ok(proxies) => {
-                // Verify proxies is not null
-                assert_ne!(proxies, json!(null), "proxies should not be null");
-
-                // Check structure - should have proxies object
-                if let Some(obj) = proxies.as_object() {
-                    if let Some(proxy_dict) = obj.get("proxies") {
-                        assert!(proxy_dict.is_object(), "proxies should be an object");
-                    }
+                for node in proxies.values() {
+                    assert!(!node.proxy_type.is_empty(), "proxy should have a type");
                 }
             }
             Err(e) => {
@@ -285,27 +814,17 @@ mod tests {
         // First get all proxies to find a valid proxy name
         match client.get_proxies().await {
             Ok(proxies) => {
-                if let Some(proxy_dict) = proxies.get("proxies") {
-                    if let Some(obj) = proxy_dict.as_object() {
-                        if let Some(first_proxy_key) = obj.keys().next() {
-                            // Test getting a specific proxy
-                            match client.get_proxy(first_proxy_key.as_str()).await {
-                                Ok(proxy) => {
-                                    assert_ne!(proxy, json!(null), "proxy should not be null");
-
-                                    if let Some(proxy_obj) = proxy.as_object() {
-                                        // Proxy should have type and name
-                                        assert!(
-                                            proxy_obj.contains_key("type")
-                                                || proxy_obj.contains_key("name"),
-                                            "proxy should have type or name"
-                                        );
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("get_proxy for {} failed: {}", first_proxy_key, e);
-                                }
-                            }
+                if let Some(first_proxy_key) = proxies.keys().next() {
+                    // Test getting a specific proxy
+                    match client.get_proxy(first_proxy_key.as_str()).await {
+                        Ok(proxy) => {
+                            assert!(
+                                !proxy.proxy_type.is_empty() || proxy.name.is_some(),
+                                "proxy should have type or name"
+                            );
+                        }
+                        Err(e) => {
+                            eprintln!("get_proxy for {} failed: {}", first_proxy_key, e);
                         }
                     }
                 }
@@ -325,22 +844,9 @@ mod tests {
 
         match client.get_rules().await {
             Ok(rules) => {
-                // Verify rules is not null
-                assert_ne!(rules, json!(null), "rules should not be null");
-
-                // Check structure - should have rules array
-                if let Some(obj) = rules.as_object() {
-                    if let Some(rules_array) = obj.get("rules") {
-                        if let Some(arr) = rules_array.as_array() {
-                            // Each rule should have at least some structure
-                            for rule in arr.iter().take(5) {
-                                assert!(
-                                    rule.is_object() || rule.is_string(),
-                                    "rule should be object or string"
-                                );
-                            }
-                        }
-                    }
+                // Each rule should have at least some structure
+                for rule in rules.iter().take(5) {
+                    assert!(!rule.rule_type.is_empty(), "rule should have a type");
                 }
             }
             Err(e) => {
@@ -403,6 +909,227 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[ignore = "requires a live mihomo instance at 127.0.0.1:9097"]
+    async fn test_log_stream() {
+        let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
+
+        match client.log_stream(None).await {
+            Ok(stream) => {
+                futures_util::pin_mut!(stream);
+                match tokio::time::timeout(std::time::Duration::from_secs(2), stream.next()).await {
+                    Ok(Some(Ok(entry))) => {
+                        assert!(!entry.log_type.is_empty(), "log entry should have a type");
+                    }
+                    Ok(Some(Err(e))) => eprintln!("log_stream yielded an error: {}", e),
+                    Ok(None) => eprintln!("log_stream ended with no events"),
+                    Err(_) => eprintln!("log_stream timed out waiting for an event"),
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "log_stream failed: {}. Skipping test (server may not be running).",
+                    e
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a live mihomo instance at 127.0.0.1:9097"]
+    async fn test_traffic_stream() {
+        let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
+
+        match client.traffic_stream().await {
+            Ok(stream) => {
+                futures_util::pin_mut!(stream);
+                match tokio::time::timeout(std::time::Duration::from_secs(2), stream.next()).await {
+                    Ok(Some(Ok(_sample))) => {}
+                    Ok(Some(Err(e))) => eprintln!("traffic_stream yielded an error: {}", e),
+                    Ok(None) => eprintln!("traffic_stream ended with no samples"),
+                    Err(_) => eprintln!("traffic_stream timed out waiting for a sample"),
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "traffic_stream failed: {}. Skipping test (server may not be running).",
+                    e
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a live mihomo instance at 127.0.0.1:9097"]
+    async fn test_memory_stream() {
+        let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
+
+        match client.memory_stream().await {
+            Ok(stream) => {
+                futures_util::pin_mut!(stream);
+                match tokio::time::timeout(std::time::Duration::from_secs(2), stream.next()).await {
+                    Ok(Some(Ok(_sample))) => {}
+                    Ok(Some(Err(e))) => eprintln!("memory_stream yielded an error: {}", e),
+                    Ok(None) => eprintln!("memory_stream ended with no samples"),
+                    Err(_) => eprintln!("memory_stream timed out waiting for a sample"),
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "memory_stream failed: {}. Skipping test (server may not be running).",
+                    e
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a live mihomo instance at 127.0.0.1:9097"]
+    async fn test_connections_stream() {
+        let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
+
+        match client.connections_stream().await {
+            Ok(stream) => {
+                futures_util::pin_mut!(stream);
+                match tokio::time::timeout(std::time::Duration::from_secs(2), stream.next()).await {
+                    Ok(Some(Ok(_snapshot))) => {}
+                    Ok(Some(Err(e))) => eprintln!("connections_stream yielded an error: {}", e),
+                    Ok(None) => eprintln!("connections_stream ended with no snapshots"),
+                    Err(_) => eprintln!("connections_stream timed out waiting for a snapshot"),
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "connections_stream failed: {}. Skipping test (server may not be running).",
+                    e
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a live mihomo instance at 127.0.0.1:9097"]
+    async fn test_get_connections() {
+        let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
+
+        match client.get_connections().await {
+            Ok(snapshot) => {
+                println!("active connections: {}", snapshot.connections.len());
+            }
+            Err(e) => {
+                eprintln!(
+                    "get_connections failed: {}. Skipping test (server may not be running).",
+                    e
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a live mihomo instance at 127.0.0.1:9097"]
+    async fn test_close_all_connections() {
+        let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
+
+        match client.close_all_connections().await {
+            Ok(()) => {}
+            Err(e) => {
+                eprintln!(
+                    "close_all_connections failed: {}. Skipping test (server may not be running).",
+                    e
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a live mihomo instance at 127.0.0.1:9097"]
+    async fn test_close_connection_not_found() {
+        let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
+
+        // Closing a bogus id should either fail or be a no-op, never panic.
+        if let Err(e) = client.close_connection("does-not-exist").await {
+            eprintln!("close_connection failed as expected: {}", e);
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a live mihomo instance at 127.0.0.1:9097"]
+    async fn test_test_group_delay() {
+        let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
+
+        // Find a group (a proxy with `all` members) to benchmark.
+        match client.get_proxies().await {
+            Ok(proxies) => {
+                if let Some((name, _)) = proxies.iter().find(|(_, p)| p.all.is_some()) {
+                    match client
+                        .test_group_delay(name, "http://www.google.com", 3000, 4)
+                        .await
+                    {
+                        Ok(results) => {
+                            assert!(!results.is_empty(), "group should have members tested");
+                        }
+                        Err(e) => {
+                            eprintln!("test_group_delay for {} failed: {}", name, e);
+                        }
+                    }
+                } else {
+                    eprintln!("no proxy group found. Skipping test.");
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "get_proxies (for group delay test) failed: {}. Skipping test.",
+                    e
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_from_profile_uses_default_profile() {
+        let mut profiles = HashMap::new();
+        profiles.insert(
+            "home".to_string(),
+            crate::settings::ProfileConf {
+                base_url: "http://127.0.0.1:9097".to_string(),
+                secret: "123456".to_string(),
+                test_url: None,
+                test_timeout_ms: None,
+                spawn: None,
+            },
+        );
+        let config = ClashtuiConfig {
+            default_profile: "home".to_string(),
+            profiles,
+        };
+
+        let client = MihomoClient::from_profile(&config, None)
+            .await
+            .expect("profile should resolve");
+        assert_eq!(client.base_url, "http://127.0.0.1:9097");
+        assert_eq!(client.secret, "123456");
+    }
+
+    #[tokio::test]
+    async fn test_from_profile_unknown_name_errors() {
+        let config = ClashtuiConfig {
+            default_profile: "home".to_string(),
+            profiles: HashMap::new(),
+        };
+
+        assert!(MihomoClient::from_profile(&config, Some("missing"))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_backoff_delay_doubles_each_attempt() {
+        let base = Duration::from_millis(100);
+        assert_eq!(backoff_delay(base, 0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(base, 1), Duration::from_millis(200));
+        assert_eq!(backoff_delay(base, 2), Duration::from_millis(400));
+    }
+
     #[tokio::test]
     async fn test_get_proxy_delay() {
         let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
@@ -410,40 +1137,23 @@ mod tests {
         // First get all proxies to find a testable proxy
         match client.get_proxies().await {
             Ok(proxies) => {
-                if let Some(proxy_dict) = proxies.get("proxies") {
-                    if let Some(obj) = proxy_dict.as_object() {
-                        // Find a proxy that's not a group (by checking if it has history)
-                        for (name, proxy) in obj.iter() {
-                            if let Some(proxy_obj) = proxy.as_object() {
-                                // Don't test if it's a GROUP type
-                                if proxy_obj.get("type").and_then(|t| t.as_str()) != Some("GROUP") {
-                                    match client
-                                        .test_proxy_delay(
-                                            name.as_str(),
-                                            "http://www.google.com",
-                                            5000,
-                                        )
-                                        .await
-                                    {
-                                        Ok(delay) => {
-                                            eprintln!("Proxy {} delay: {}ms", name, delay);
-                                            // Delay should be reasonable (0 to 10000ms, or timeout indicator)
-                                            assert!(
-                                                delay <= 10000 || delay > 0,
-                                                "delay should be reasonable"
-                                            );
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "test_proxy_delay for {} failed: {}",
-                                                name, e
-                                            );
-                                        }
-                                    }
-                                    break; // Test first non-group proxy
-                                }
+                // Find a proxy that's not a group (GROUP nodes don't carry delay).
+                for (name, proxy) in proxies.iter() {
+                    if proxy.proxy_type != "GROUP" {
+                        match client
+                            .test_proxy_delay(name.as_str(), "http://www.google.com", 5000)
+                            .await
+                        {
+                            Ok(delay) => {
+                                eprintln!("Proxy {} delay: {}ms", name, delay);
+                                // Delay should be reasonable (0 to 10000ms)
+                                assert!(delay <= 10000, "delay should be reasonable");
+                            }
+                            Err(e) => {
+                                eprintln!("test_proxy_delay for {} failed: {}", name, e);
                             }
                         }
+                        break; // Test first non-group proxy
                     }
                 }
             }
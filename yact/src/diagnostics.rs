@@ -0,0 +1,80 @@
+// Tracing subscriber that mirrors formatted events into an in-memory ring
+// buffer the UI can render, giving the TUI visibility into its own internal
+// errors without threading log strings through every caller by hand.
+
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Max number of formatted diagnostic lines kept in memory.
+const MAX_DIAGNOSTIC_LINES: usize = 500;
+
+/// Shared, cloneable handle onto the diagnostics ring buffer. The global
+/// tracing subscriber writes into it; `AppState` holds a clone to read from.
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticsBuffer(Arc<Mutex<VecDeque<String>>>);
+
+impl DiagnosticsBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of all currently buffered lines, oldest first.
+    pub fn snapshot(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .expect("diagnostics buffer poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    fn push_line(&self, line: &str) {
+        let mut buffer = self.0.lock().expect("diagnostics buffer poisoned");
+        buffer.push_back(line.to_string());
+        while buffer.len() > MAX_DIAGNOSTIC_LINES {
+            buffer.pop_front();
+        }
+    }
+}
+
+struct DiagnosticsWriter(DiagnosticsBuffer);
+
+impl Write for DiagnosticsWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for line in String::from_utf8_lossy(buf).lines() {
+            if !line.is_empty() {
+                self.0.push_line(line);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct DiagnosticsMakeWriter(DiagnosticsBuffer);
+
+impl<'a> MakeWriter<'a> for DiagnosticsMakeWriter {
+    type Writer = DiagnosticsWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        DiagnosticsWriter(self.0.clone())
+    }
+}
+
+/// Install a global tracing subscriber that formats events and mirrors them
+/// into `buffer`. Must be called once, before any `tracing::*!` calls.
+pub fn init(buffer: DiagnosticsBuffer) {
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(DiagnosticsMakeWriter(buffer))
+        .with_ansi(false)
+        .without_time()
+        .finish();
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
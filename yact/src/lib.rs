@@ -0,0 +1,11 @@
+// Library surface for yact: the Mihomo API client plus the TUI modules that
+// drive it. Split out from main.rs so the client (and the types it touches)
+// is usable/testable independent of the binary, and so items only exercised
+// by `#[cfg(test)]` aren't flagged as dead code in a binary-only crate.
+
+pub mod api;
+pub mod diagnostics;
+pub mod log_file;
+pub mod model;
+pub mod settings;
+pub mod ui;
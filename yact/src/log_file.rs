@@ -0,0 +1,206 @@
+// Rolling log file writer used to persist streamed Mihomo logs to disk.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc as std_mpsc;
+
+use tokio::sync::mpsc as tokio_mpsc;
+
+use crate::ui::AppMessage;
+
+/// Default byte size at which the active log file rotates.
+pub const DEFAULT_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// Default number of rotated files kept (`clash.log.1` .. `clash.log.N`).
+pub const DEFAULT_MAX_ROTATIONS: usize = 5;
+
+/// Default path for the rolling clash log under the user's cache dir.
+pub fn default_log_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("clashtui")
+        .join("clash.log")
+}
+
+/// Buffered writer that rotates to `<path>.1`, `.2`, ... once the active file
+/// reaches `max_bytes`, keeping only the last `max_rotations` files.
+struct RollingLogWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_rotations: usize,
+    writer: BufWriter<File>,
+    written: u64,
+}
+
+impl RollingLogWriter {
+    fn open(path: PathBuf, max_bytes: u64, max_rotations: usize) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            max_rotations,
+            writer: BufWriter::new(file),
+            written,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.written += line.len() as u64 + 1;
+
+        if self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+
+        for index in (1..self.max_rotations).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.writer = BufWriter::new(file);
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(base: &Path, index: usize) -> PathBuf {
+    let mut name = base.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// Handle to a background thread mirroring log lines to disk. Writing
+/// happens off the render thread; `send_line` is a cheap, non-blocking push
+/// onto the writer's channel.
+pub struct LogFileHandle {
+    sender: std_mpsc::Sender<String>,
+}
+
+impl LogFileHandle {
+    pub fn spawn(
+        path: PathBuf,
+        max_bytes: u64,
+        max_rotations: usize,
+        on_error: tokio_mpsc::UnboundedSender<AppMessage>,
+    ) -> Self {
+        let (sender, receiver) = std_mpsc::channel::<String>();
+
+        std::thread::spawn(move || {
+            let mut writer = match RollingLogWriter::open(path, max_bytes, max_rotations) {
+                Ok(writer) => writer,
+                Err(e) => {
+                    let _ =
+                        on_error.send(AppMessage::Error(format!("Failed to open log file: {}", e)));
+                    return;
+                }
+            };
+
+            while let Ok(line) = receiver.recv() {
+                if let Err(e) = writer.write_line(&line) {
+                    let _ = on_error.send(AppMessage::Error(format!(
+                        "Failed to write log file: {}",
+                        e
+                    )));
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    /// Queue a line for the writer thread. Dropped silently if the writer
+    /// thread has already exited.
+    pub fn send_line(&self, line: &str) {
+        let _ = self.sender.send(line.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// A fresh, non-existent path under the system temp dir for each test, so
+    /// concurrent test runs (and rotated siblings) don't collide.
+    fn temp_log_path() -> PathBuf {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir()
+            .join("yact-log-file-tests")
+            .join(format!("{}-{}.log", std::process::id(), n))
+    }
+
+    fn read_to_string(path: &Path) -> String {
+        fs::read_to_string(path).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_write_line_appends_without_rotating_below_max_bytes() {
+        let path = temp_log_path();
+        let mut writer = RollingLogWriter::open(path.clone(), 1024, 5).unwrap();
+
+        writer.write_line("first").unwrap();
+        writer.write_line("second").unwrap();
+
+        assert_eq!(read_to_string(&path), "first\nsecond\n");
+        assert!(!rotated_path(&path, 1).exists());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_write_line_rotates_once_max_bytes_exceeded() {
+        let path = temp_log_path();
+        // "first\n" is 6 bytes, which meets max_bytes on its own, so every
+        // write here triggers a rotate right after landing.
+        let mut writer = RollingLogWriter::open(path.clone(), 6, 5).unwrap();
+
+        writer.write_line("first").unwrap();
+        writer.write_line("second").unwrap();
+
+        assert_eq!(read_to_string(&path), "");
+        assert_eq!(read_to_string(&rotated_path(&path, 1)), "second\n");
+        assert_eq!(read_to_string(&rotated_path(&path, 2)), "first\n");
+
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(rotated_path(&path, 1));
+        let _ = fs::remove_file(rotated_path(&path, 2));
+    }
+
+    #[test]
+    fn test_write_line_keeps_only_max_rotations_files() {
+        let path = temp_log_path();
+        let mut writer = RollingLogWriter::open(path.clone(), 1, 2).unwrap();
+
+        // Each line exceeds max_bytes on its own, so every write rotates.
+        writer.write_line("one").unwrap();
+        writer.write_line("two").unwrap();
+        writer.write_line("three").unwrap();
+
+        assert_eq!(read_to_string(&rotated_path(&path, 1)), "three\n");
+        assert_eq!(read_to_string(&rotated_path(&path, 2)), "two\n");
+        assert!(!rotated_path(&path, 3).exists());
+
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(rotated_path(&path, 1));
+        let _ = fs::remove_file(rotated_path(&path, 2));
+    }
+}
@@ -1,17 +1,23 @@
 // clashtui/yact/src/main.rs
 
-mod api;
-mod ui;
-
-use ui::*;
+use yact::diagnostics;
+use yact::ui::{self, *};
 
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
     execute,
-    terminal::{Clear, ClearType, disable_raw_mode, enable_raw_mode},
+    terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType},
 };
+use std::time::Duration;
+
+/// How often the loop wakes on its own to drain background messages even
+/// when no key was pressed.
+const TICK_RATE: Duration = Duration::from_millis(100);
 
 fn main() -> std::io::Result<()> {
+    let diagnostics = diagnostics::DiagnosticsBuffer::new();
+    diagnostics::init(diagnostics.clone());
+
     let mut stdout = std::io::stdout();
     enable_raw_mode()?;
 
@@ -20,45 +26,102 @@ fn main() -> std::io::Result<()> {
     let backend = ratatui::backend::CrosstermBackend::new(stdout);
     let mut terminal = ratatui::Terminal::new(backend)?;
 
-    let mut app = AppState::new();
+    let mut app = AppState::new(diagnostics);
     let mut running = true;
 
     while running {
-        terminal.draw(|frame| {
-            render_ui(frame, &mut app);
-        })?;
-
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                match key.code {
-                    KeyCode::Left => app.previous_page(),
-                    KeyCode::Right => app.next_page(),
-                    KeyCode::Up => app.scroll_up(),
-                    KeyCode::Down => app.scroll_down(),
-                    KeyCode::Char('r') | KeyCode::Char('R') => {
-                        if app.current_page == ui::AppPage::Config {
-                            app.configs = None;
-                            app.load_configs();
-                        }
+        if event::poll(TICK_RATE)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && app.search_active {
+                    match key.code {
+                        KeyCode::Char(c) => app.search_push_char(c),
+                        KeyCode::Backspace => app.search_backspace(),
+                        KeyCode::Enter => app.search_active = false,
+                        KeyCode::Esc => app.cancel_search(),
+                        _ => {}
                     }
-                    KeyCode::Enter => {
-                        if app.current_page == ui::AppPage::Config && app.configs.is_none() {
+                } else if key.kind == KeyEventKind::Press && app.settings_editing {
+                    match key.code {
+                        KeyCode::Char(c) => app.settings_push_char(c),
+                        KeyCode::Backspace => app.settings_backspace(),
+                        KeyCode::Tab => app.settings_toggle_focus(),
+                        KeyCode::Enter => app.confirm_settings_edit(),
+                        KeyCode::Esc => app.cancel_settings_edit(),
+                        _ => {}
+                    }
+                } else if key.kind == KeyEventKind::Press {
+                    match key.code {
+                        KeyCode::Left => app.previous_page(),
+                        KeyCode::Right => app.next_page(),
+                        KeyCode::Up => {
+                            if app.current_page == ui::AppPage::Log {
+                                app.scroll_logs_up();
+                            } else {
+                                app.scroll_up();
+                            }
+                        }
+                        KeyCode::Down => {
+                            if app.current_page == ui::AppPage::Log {
+                                app.scroll_logs_down();
+                            } else {
+                                app.scroll_down();
+                            }
+                        }
+                        KeyCode::Char('r') | KeyCode::Char('R') => {
+                            if app.current_page == ui::AppPage::Config {
+                                app.configs = None;
+                                app.load_configs();
+                            } else if app.current_page == ui::AppPage::Settings {
+                                app.reload_settings();
+                            }
+                        }
+                        KeyCode::Char('e') | KeyCode::Char('E')
+                            if app.current_page == ui::AppPage::Settings =>
+                        {
+                            app.start_settings_edit();
+                        }
+                        KeyCode::Enter
+                            if app.current_page == ui::AppPage::Config && app.configs.is_none() =>
+                        {
                             app.load_configs();
                         }
-                    }
-                    KeyCode::Char('q') | KeyCode::Char('Q') => running = false,
-                    KeyCode::Esc => running = false,
-                    KeyCode::Char('l') | KeyCode::Char('L') => {
-                        if app.current_page == ui::AppPage::Log {
-                            app.load_logs();
+                        KeyCode::Char('q') | KeyCode::Char('Q') => running = false,
+                        KeyCode::Esc => running = false,
+                        KeyCode::Char('s') | KeyCode::Char('S')
+                            if app.current_page == ui::AppPage::Log =>
+                        {
+                            app.toggle_log_stream();
+                        }
+                        KeyCode::Char('/') if app.current_page == ui::AppPage::Log => {
+                            app.start_search();
+                        }
+                        KeyCode::Char('f') | KeyCode::Char('F')
+                            if app.current_page == ui::AppPage::Log =>
+                        {
+                            app.toggle_log_persistence();
+                        }
+                        KeyCode::Char(digit @ '1'..='4')
+                            if app.current_page == ui::AppPage::Log =>
+                        {
+                            if let Some(level) = ui::LogLevel::from_key(digit) {
+                                app.toggle_level_filter(level);
+                            }
                         }
+                        _ => {}
                     }
-                    _ => {}
                 }
             }
         }
+
+        app.drain_messages();
+
+        terminal.draw(|frame| {
+            render_ui(frame, &mut app);
+        })?;
     }
 
+    app.stop_log_stream();
+
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), Clear(ClearType::All))?;
     terminal.show_cursor()?;
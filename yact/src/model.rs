@@ -0,0 +1,80 @@
+// Typed domain models for Mihomo API responses, so callers deserialize into
+// checked structs instead of threading `serde_json::Value` everywhere and
+// guessing field names with `as_object()`/`contains_key()`. `Configs` and
+// `ProxyNode` keep a flattened `extra` map that collects fields they don't
+// name (e.g. ones added by newer Mihomo versions); the other structs simply
+// ignore unknown fields.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// `GET /configs` response.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Configs {
+    #[serde(rename = "mixed-port", default)]
+    pub mixed_port: Option<u16>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(rename = "socks-port", default)]
+    pub socks_port: Option<u16>,
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(rename = "log-level", default)]
+    pub log_level: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A single point in a proxy's delay history, as returned under `history`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DelayHistory {
+    pub time: String,
+    pub delay: u64,
+}
+
+/// A proxy node or group as returned by `GET /proxies` / `GET /proxies/:name`.
+/// Mihomo represents both shapes with the same JSON schema (groups just
+/// additionally carry `now`/`all`), so one struct covers both; `ProxyGroup`
+/// is kept as an alias for callers that only ever deal with groups.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProxyNode {
+    #[serde(rename = "type")]
+    pub proxy_type: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub now: Option<String>,
+    #[serde(default)]
+    pub all: Option<Vec<String>>,
+    #[serde(default)]
+    pub history: Vec<DelayHistory>,
+    #[serde(default)]
+    pub udp: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Alias for `ProxyNode` used where a value is known to be a group (it
+/// carries `now`/`all`) rather than a leaf proxy.
+pub type ProxyGroup = ProxyNode;
+
+/// `GET /proxies` response, keyed by proxy/group name.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProxiesResponse {
+    pub proxies: std::collections::HashMap<String, ProxyNode>,
+}
+
+/// A single routing rule as returned under `GET /rules`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    #[serde(rename = "type")]
+    pub rule_type: String,
+    pub payload: String,
+    pub proxy: String,
+}
+
+/// `GET /rules` response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RulesResponse {
+    pub rules: Vec<Rule>,
+}
@@ -0,0 +1,123 @@
+// User-editable Mihomo controller settings for the TUI, loaded from a TOML
+// file in the user's config directory so clashtui isn't locked to a single
+// hardcoded address and secret. The same file also carries optional named
+// profiles (and an optional core-process spawn directive per profile) for
+// callers that want to juggle several mihomo instances via
+// `MihomoClient::from_profile` instead of the single controller the TUI edits.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Resolved controller address/secret used for every Mihomo API call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControllerSettings {
+    pub base_url: String,
+    pub secret: String,
+}
+
+impl Default for ControllerSettings {
+    fn default() -> Self {
+        Self {
+            base_url: "http://127.0.0.1:9097".to_string(),
+            secret: "123456".to_string(),
+        }
+    }
+}
+
+/// Optional directive to launch/attach a local core process before connecting.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnConf {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub envs: HashMap<String, String>,
+}
+
+impl SpawnConf {
+    /// Launch the configured core process. Stdio is inherited, so the core's
+    /// own output goes straight to the terminal; it is not captured into the
+    /// diagnostics pane.
+    pub fn spawn(&self) -> Result<Child> {
+        Command::new(&self.command)
+            .args(&self.args)
+            .envs(&self.envs)
+            .spawn()
+            .with_context(|| format!("failed to spawn core process: {}", self.command))
+    }
+}
+
+/// A single named mihomo endpoint, as listed under `[profiles.<name>]`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileConf {
+    pub base_url: String,
+    pub secret: String,
+    #[serde(default)]
+    pub test_url: Option<String>,
+    #[serde(default)]
+    pub test_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub spawn: Option<SpawnConf>,
+}
+
+/// The named-profiles view of the settings file, for callers (e.g.
+/// `MihomoClient::from_profile`) that juggle more than one controller.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClashtuiConfig {
+    pub default_profile: String,
+    pub profiles: HashMap<String, ProfileConf>,
+}
+
+impl ClashtuiConfig {
+    /// Look up a named profile, or `default_profile` when `name` is `None`.
+    pub fn profile(&self, name: Option<&str>) -> Result<&ProfileConf> {
+        let key = name.unwrap_or(&self.default_profile);
+        self.profiles
+            .get(key)
+            .with_context(|| format!("no such profile: {}", key))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SettingsFile {
+    base_url: Option<String>,
+    secret: Option<String>,
+}
+
+/// Default path for the settings file under the user's config dir.
+pub fn default_settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("clashtui")
+        .join("settings.toml")
+}
+
+/// Load controller settings from `path`. Falls back to defaults for any
+/// field that's absent, or entirely if the file is missing or malformed.
+pub fn load(path: &Path) -> ControllerSettings {
+    let defaults = ControllerSettings::default();
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return defaults;
+    };
+
+    let file = toml::from_str::<SettingsFile>(&text).unwrap_or_default();
+    ControllerSettings {
+        base_url: file.base_url.unwrap_or(defaults.base_url),
+        secret: file.secret.unwrap_or(defaults.secret),
+    }
+}
+
+/// Load the named-profiles view of the settings file at `path`, for callers
+/// that want `MihomoClient::from_profile` instead of the single controller
+/// the TUI's Settings page edits.
+pub fn load_config(path: &Path) -> Result<ClashtuiConfig> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read settings file: {}", path.display()))?;
+
+    toml::from_str(&text)
+        .with_context(|| format!("failed to parse settings file: {}", path.display()))
+}
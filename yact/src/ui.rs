@@ -1,15 +1,140 @@
 // UI rendering module for ratatui application
 
+use ansi_to_tui::IntoText;
+use futures_util::StreamExt;
 use ratatui::{
-    Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Tabs},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Paragraph, ScrollbarState, Tabs},
+    Frame,
 };
 use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
 
-// Import api module (declared in main.rs)
+// Import api/diagnostics/log_file/settings modules (declared in lib.rs)
 use crate::api::MihomoClient;
+use crate::diagnostics::DiagnosticsBuffer;
+use crate::log_file::{self, LogFileHandle};
+use crate::settings::{self, ControllerSettings};
+
+/// Maximum number of pending `AppMessage`s drained from the channel per tick.
+/// Keeps a burst of log lines from starving the render loop.
+const MAX_MESSAGES_PER_TICK: usize = 64;
+
+/// Max number of log lines kept in the ring buffer.
+const MAX_LOG_LINES: usize = 1000;
+
+/// A log line as kept in `AppState`, with its level resolved for coloring.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub level: String,
+    pub payload: String,
+}
+
+impl From<crate::api::LogEntry> for LogEntry {
+    /// `api::LogEntry` is already decoded off the SSE stream by
+    /// `MihomoClient::log_stream`; this just renames `log_type` to the
+    /// `level` this module colors/filters by.
+    fn from(entry: crate::api::LogEntry) -> Self {
+        LogEntry {
+            level: entry.log_type,
+            payload: entry.payload,
+        }
+    }
+}
+
+impl LogEntry {
+    /// Plain-text representation used when mirroring to the log file.
+    fn display_line(&self) -> String {
+        if self.level.is_empty() {
+            self.payload.clone()
+        } else {
+            format!("[{}] {}", self.level, self.payload)
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self.level.as_str() {
+            "error" => Color::Red,
+            "warning" => Color::Yellow,
+            "info" => Color::Green,
+            "debug" => Color::Gray,
+            _ => Color::White,
+        }
+    }
+
+    /// Render this entry as styled `Line`s, decoding embedded ANSI escapes
+    /// when present (a payload with embedded newlines decodes to more than
+    /// one `Line`, all of which are kept) and falling back to a single plain
+    /// colored line otherwise.
+    fn to_lines(&self) -> Vec<Line<'static>> {
+        if self.payload.contains('\x1b') {
+            if let Ok(text) = self.payload.clone().into_text() {
+                if !text.lines.is_empty() {
+                    return text.lines;
+                }
+            }
+        }
+        vec![Line::from(Span::styled(
+            self.payload.clone(),
+            Style::default().fg(self.color()),
+        ))]
+    }
+}
+
+/// Minimum-level filter for the Log page, cycled with keys `1`-`4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    pub fn from_key(digit: char) -> Option<Self> {
+        match digit {
+            '1' => Some(LogLevel::Debug),
+            '2' => Some(LogLevel::Info),
+            '3' => Some(LogLevel::Warning),
+            '4' => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    fn from_str(level: &str) -> Option<Self> {
+        match level {
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warning" => Some(LogLevel::Warning),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warning => "warning",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+/// Messages produced by background tasks and consumed on the render thread.
+#[derive(Debug)]
+pub enum AppMessage {
+    ConfigsLoaded(Value),
+    LogLine(LogEntry),
+    Error(String),
+    ConfigsLoading(bool),
+    LogsLoading(bool),
+    StreamStopped,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppPage {
@@ -56,6 +181,22 @@ impl AppPage {
     }
 }
 
+/// Which field the Settings page's edit form is focused on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsField {
+    Address,
+    Secret,
+}
+
+impl SettingsField {
+    fn toggled(self) -> Self {
+        match self {
+            SettingsField::Address => SettingsField::Secret,
+            SettingsField::Secret => SettingsField::Address,
+        }
+    }
+}
+
 pub struct AppState {
     pub current_page: AppPage,
     pub configs: Option<Value>,
@@ -63,15 +204,34 @@ pub struct AppState {
     pub error: Option<String>,
     pub scroll_offset: u16,
     pub scroll_state: ScrollbarState,
-    pub stdout_output: String,
-    pub logs: Option<String>,
+    pub diagnostics: DiagnosticsBuffer,
+    pub logs: VecDeque<LogEntry>,
     pub logs_loading: bool,
+    pub streaming: bool,
+    pub auto_scroll: bool,
+    pub log_scroll_offset: u16,
+    pub min_level: Option<LogLevel>,
+    pub search_active: bool,
+    pub search_query: String,
+    pub log_file_path: PathBuf,
+    pub log_file_max_bytes: u64,
+    pub settings_path: PathBuf,
+    pub controller: ControllerSettings,
+    pub settings_editing: bool,
+    pub settings_focus: SettingsField,
+    pub settings_draft_address: String,
+    pub settings_draft_secret: String,
+    log_file: Option<LogFileHandle>,
+    stream_handle: Option<tokio::task::JoinHandle<()>>,
+    tx: mpsc::UnboundedSender<AppMessage>,
+    rx: mpsc::UnboundedReceiver<AppMessage>,
     runtime: tokio::runtime::Runtime,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub fn new(diagnostics: DiagnosticsBuffer) -> Self {
         let runtime = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+        let (tx, rx) = mpsc::unbounded_channel();
 
         Self {
             current_page: AppPage::Proxy,
@@ -80,111 +240,270 @@ impl AppState {
             error: None,
             scroll_offset: 0,
             scroll_state: ScrollbarState::new(0),
-            stdout_output: String::new(),
-            logs: None,
+            diagnostics,
+            logs: VecDeque::new(),
             logs_loading: false,
+            streaming: false,
+            auto_scroll: true,
+            log_scroll_offset: 0,
+            min_level: None,
+            search_active: false,
+            search_query: String::new(),
+            log_file_path: log_file::default_log_path(),
+            log_file_max_bytes: log_file::DEFAULT_MAX_BYTES,
+            settings_path: settings::default_settings_path(),
+            controller: settings::load(&settings::default_settings_path()),
+            settings_editing: false,
+            settings_focus: SettingsField::Address,
+            settings_draft_address: String::new(),
+            settings_draft_secret: String::new(),
+            log_file: None,
+            stream_handle: None,
+            tx,
+            rx,
             runtime,
         }
     }
 
-    pub fn update_stdout(&mut self, output: String) {
-        self.stdout_output = output;
+    /// Drain up to `MAX_MESSAGES_PER_TICK` pending messages from background
+    /// tasks and apply them to state. Called once per render tick so a flood
+    /// of log lines can't starve drawing.
+    pub fn drain_messages(&mut self) {
+        for _ in 0..MAX_MESSAGES_PER_TICK {
+            match self.rx.try_recv() {
+                Ok(message) => self.apply_message(message),
+                Err(_) => break,
+            }
+        }
     }
 
-    pub fn clear_stdout(&mut self) {
-        self.stdout_output.clear();
+    fn apply_message(&mut self, message: AppMessage) {
+        match message {
+            AppMessage::ConfigsLoaded(configs) => {
+                let text = serde_json::to_string_pretty(&configs).unwrap_or_default();
+                let lines = text.lines().count();
+                self.configs = Some(configs);
+                self.scroll_state = ScrollbarState::new(lines.saturating_sub(1));
+            }
+            AppMessage::LogLine(entry) => {
+                if let Some(file) = &self.log_file {
+                    file.send_line(&entry.display_line());
+                }
+                self.logs.push_back(entry);
+                while self.logs.len() > MAX_LOG_LINES {
+                    self.logs.pop_front();
+                }
+                if self.auto_scroll {
+                    self.log_scroll_offset = self.logs.len().saturating_sub(1) as u16;
+                }
+            }
+            AppMessage::Error(message) => {
+                self.error = Some(message);
+            }
+            AppMessage::ConfigsLoading(is_loading) => {
+                self.loading = is_loading;
+            }
+            AppMessage::LogsLoading(is_loading) => {
+                self.logs_loading = is_loading;
+            }
+            AppMessage::StreamStopped => {
+                self.streaming = false;
+            }
+        }
     }
 
-    /// Parse SSE format and extract log messages
-    fn parse_sse_logs(&self, sse_data: &str) -> String {
-        let mut lines = Vec::new();
-        for line in sse_data.lines() {
-            // Skip SSE meta lines (event:, id:, retry:)
-            if line.starts_with("event:") || line.starts_with("id:") || line.starts_with("retry:") {
-                continue;
-            }
-            // Extract data content
-            let content = if line.starts_with("data:") {
-                line.strip_prefix("data:").unwrap_or(line).trim_start()
-            } else {
-                line
+    /// Start streaming `/logs` in the background. A no-op if already streaming.
+    ///
+    /// `/logs` is an open-ended SSE stream, so there is no one-shot
+    /// "load once" equivalent — `'s'` streaming is the only way to see logs.
+    pub fn start_log_stream(&mut self) {
+        if self.streaming {
+            return;
+        }
+        self.streaming = true;
+        self.error = None;
+        let tx = self.tx.clone();
+        let controller = self.controller.clone();
+
+        let handle = self.runtime.spawn(async move {
+            tracing::info!("Starting log stream");
+            let client = MihomoClient::new(controller.base_url, controller.secret);
+            let stream = match client.log_stream(None).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::error!("Failed to start log stream: {}", e);
+                    let _ = tx.send(AppMessage::Error(format!(
+                        "Failed to start log stream: {}",
+                        e
+                    )));
+                    let _ = tx.send(AppMessage::StreamStopped);
+                    return;
+                }
             };
-            if !content.is_empty() {
-                lines.push(content.to_string());
+
+            futures_util::pin_mut!(stream);
+            while let Some(entry) = stream.next().await {
+                match entry {
+                    Ok(entry) => {
+                        let _ = tx.send(AppMessage::LogLine(entry.into()));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Log stream error, reconnect required: {}", e);
+                        let _ = tx.send(AppMessage::Error(format!("Log stream error: {}", e)));
+                        break;
+                    }
+                }
             }
+
+            tracing::info!("Log stream ended");
+            let _ = tx.send(AppMessage::StreamStopped);
+        });
+
+        self.stream_handle = Some(handle);
+    }
+
+    /// Stop the background log stream, if one is running.
+    pub fn stop_log_stream(&mut self) {
+        if let Some(handle) = self.stream_handle.take() {
+            handle.abort();
         }
-        lines.join("\n")
+        self.streaming = false;
+        tracing::info!("Stopped log stream");
     }
 
-    /// Limit logs to last N lines to prevent memory bloat
-    fn limit_log_lines(&self, logs: &str, max_lines: usize) -> String {
-        let all_lines: Vec<&str> = logs.lines().collect();
-        if all_lines.len() <= max_lines {
-            return logs.to_string();
+    pub fn toggle_log_stream(&mut self) {
+        if self.streaming {
+            self.stop_log_stream();
+        } else {
+            self.start_log_stream();
         }
-        let start_index = all_lines.len() - max_lines;
-        all_lines[start_index..].join("\n")
     }
 
-    pub fn load_logs(&mut self) {
-        self.logs_loading = true;
+    pub fn log_file_enabled(&self) -> bool {
+        self.log_file.is_some()
+    }
 
-        let result = self.runtime.block_on(async {
-            let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
-            client.get_logs(None).await
-        });
+    /// Toggle mirroring streamed log lines to the rolling log file at
+    /// `self.log_file_path`. Dropping the handle ends the writer thread.
+    pub fn toggle_log_persistence(&mut self) {
+        if self.log_file.is_some() {
+            self.log_file = None;
+        } else {
+            self.log_file = Some(LogFileHandle::spawn(
+                self.log_file_path.clone(),
+                self.log_file_max_bytes,
+                log_file::DEFAULT_MAX_ROTATIONS,
+                self.tx.clone(),
+            ));
+        }
+    }
 
-        match result {
-            Ok(resp) => {
-                // For SSE streaming response, read all chunks
-                let body = self.runtime.block_on(async { resp.text().await });
-                match body {
-                    Ok(text) => {
-                        // Parse SSE format
-                        let parsed_logs = self.parse_sse_logs(&text);
-                        // Limit to last MAX_LOG_LINES lines
-                        let limited_logs = self.limit_log_lines(&parsed_logs, 1000);
-
-                        // Append to existing logs or create new
-                        if let Some(existing) = &self.logs {
-                            let combined = format!("{}\n{}", existing, limited_logs);
-                            // Limit combined logs too
-                            self.logs = Some(self.limit_log_lines(&combined, 1000));
-                        } else {
-                            self.logs = Some(limited_logs);
-                        }
-                    }
-                    Err(e) => {
-                        let error_msg = format!("Error reading logs: {}", e);
-                        if let Some(ref mut existing) = self.logs {
-                            existing.push_str("\n");
-                            existing.push_str(&error_msg);
-                        } else {
-                            self.logs = Some(error_msg);
-                        }
-                    }
-                }
+    /// Reload controller settings from `self.settings_path`, discarding any
+    /// in-progress edit.
+    pub fn reload_settings(&mut self) {
+        self.controller = settings::load(&self.settings_path);
+        self.settings_editing = false;
+        tracing::info!("Reloaded controller settings from {:?}", self.settings_path);
+    }
+
+    pub fn start_settings_edit(&mut self) {
+        self.settings_editing = true;
+        self.settings_focus = SettingsField::Address;
+        self.settings_draft_address = self.controller.base_url.clone();
+        self.settings_draft_secret = self.controller.secret.clone();
+    }
+
+    pub fn cancel_settings_edit(&mut self) {
+        self.settings_editing = false;
+    }
+
+    pub fn settings_toggle_focus(&mut self) {
+        self.settings_focus = self.settings_focus.toggled();
+    }
+
+    pub fn settings_push_char(&mut self, c: char) {
+        match self.settings_focus {
+            SettingsField::Address => self.settings_draft_address.push(c),
+            SettingsField::Secret => self.settings_draft_secret.push(c),
+        }
+    }
+
+    pub fn settings_backspace(&mut self) {
+        match self.settings_focus {
+            SettingsField::Address => {
+                self.settings_draft_address.pop();
             }
-            Err(e) => {
-                let error_msg = format!("Failed to load logs: {}", e);
-                if let Some(ref mut existing) = self.logs {
-                    existing.push_str("\n");
-                    existing.push_str(&error_msg);
-                } else {
-                    self.logs = Some(error_msg);
-                }
+            SettingsField::Secret => {
+                self.settings_draft_secret.pop();
             }
         }
+    }
 
-        self.logs_loading = false;
+    /// Apply the in-progress edit to `self.controller` and exit edit mode.
+    pub fn confirm_settings_edit(&mut self) {
+        self.controller = ControllerSettings {
+            base_url: self.settings_draft_address.clone(),
+            secret: self.settings_draft_secret.clone(),
+        };
+        self.settings_editing = false;
+        tracing::info!("Applied controller settings: {}", self.controller.base_url);
     }
 
     pub fn scroll_logs_up(&mut self) {
-        // Implement log-specific scrolling if needed
+        self.auto_scroll = false;
+        self.log_scroll_offset = self.log_scroll_offset.saturating_sub(1);
     }
 
     pub fn scroll_logs_down(&mut self) {
-        // Implement log-specific scrolling if needed
+        let max_offset = self.logs.len().saturating_sub(1) as u16;
+        self.log_scroll_offset = (self.log_scroll_offset + 1).min(max_offset);
+        if self.log_scroll_offset >= max_offset {
+            self.auto_scroll = true;
+        }
+    }
+
+    /// Toggle the minimum-level filter: pressing the key for the active level
+    /// again clears the filter.
+    pub fn toggle_level_filter(&mut self, level: LogLevel) {
+        self.min_level = if self.min_level == Some(level) {
+            None
+        } else {
+            Some(level)
+        };
+    }
+
+    pub fn start_search(&mut self) {
+        self.search_active = true;
+    }
+
+    pub fn cancel_search(&mut self) {
+        self.search_active = false;
+        self.search_query.clear();
+    }
+
+    pub fn search_push_char(&mut self, c: char) {
+        self.search_query.push(c);
+    }
+
+    pub fn search_backspace(&mut self) {
+        self.search_query.pop();
+    }
+
+    /// Entries passing the active level filter and search query. Computed at
+    /// render time so toggling filters never discards the underlying buffer.
+    fn visible_logs(&self) -> Vec<&LogEntry> {
+        let query = self.search_query.to_lowercase();
+        self.logs
+            .iter()
+            .filter(|entry| {
+                let level_ok = match self.min_level {
+                    Some(min) => LogLevel::from_str(&entry.level).is_none_or(|l| l >= min),
+                    None => true,
+                };
+                let query_ok = query.is_empty() || entry.payload.to_lowercase().contains(&query);
+                level_ok && query_ok
+            })
+            .collect()
     }
 
     pub fn next_page(&mut self) {
@@ -202,29 +521,26 @@ impl AppState {
     pub fn load_configs(&mut self) {
         self.loading = true;
         self.error = None;
-
-        // Use tokio runtime to execute async API call
-        let result = self.runtime.block_on(async {
-            let client = MihomoClient::new("http://127.0.0.1:9097", "123456");
-            client.get_configs().await
-        });
-
-        match result {
-            Ok(configs) => {
-                self.configs = Some(configs);
-                // Count lines for scrollbar
-                if let Some(ref configs) = self.configs {
-                    let text = serde_json::to_string_pretty(configs).unwrap_or_default();
-                    let lines = text.lines().count();
-                    self.scroll_state = ScrollbarState::new(lines.saturating_sub(1));
+        let tx = self.tx.clone();
+
+        // Spawn the request onto the owned runtime; the render loop never blocks
+        // on it and instead drains the result back via `AppMessage`.
+        let controller = self.controller.clone();
+        self.runtime.spawn(async move {
+            tracing::info!("Loading configs");
+            let client = MihomoClient::new(controller.base_url, controller.secret);
+            match client.get_configs().await {
+                Ok(configs) => {
+                    let configs = serde_json::to_value(&configs).unwrap_or(Value::Null);
+                    let _ = tx.send(AppMessage::ConfigsLoaded(configs));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load configs: {}", e);
+                    let _ = tx.send(AppMessage::Error(format!("Failed to load configs: {}", e)));
                 }
             }
-            Err(e) => {
-                self.error = Some(format!("Failed to load configs: {}", e));
-            }
-        }
-
-        self.loading = false;
+            let _ = tx.send(AppMessage::ConfigsLoading(false));
+        });
     }
 
     pub fn scroll_down(&mut self) {
@@ -246,29 +562,107 @@ fn render_proxy_page(f: &mut Frame, area: Rect) {
 }
 
 fn render_log_page(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let visible = app.visible_logs();
+
+    let mut title = if app.streaming {
+        "Log (streaming, 's' to stop)".to_string()
+    } else {
+        "Log ('s' to stream)".to_string()
+    };
+    if app.log_file_enabled() {
+        title.push_str(" [file:on]");
+    }
+    if let Some(level) = app.min_level {
+        title.push_str(&format!(" [min:{}]", level.label()));
+    }
+    if !app.search_query.is_empty() {
+        title.push_str(&format!(" [search:\"{}\"]", app.search_query));
+    }
+    if app.min_level.is_some() || !app.search_query.is_empty() {
+        title.push_str(&format!(" ({}/{})", visible.len(), app.logs.len()));
+    }
+
+    let (log_area, search_area) = if app.search_active {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Min(1), Constraint::Length(1)])
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    } else {
+        (area, None)
+    };
+
     let content = if app.logs_loading {
         Paragraph::new("Loading logs...".to_string())
             .alignment(Alignment::Center)
             .style(Style::default().fg(Color::Yellow))
-    } else if let Some(ref logs) = app.logs {
-        Paragraph::new(logs.clone())
+    } else if let Some(ref error) = app.error {
+        Paragraph::new(error.clone())
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Red))
+    } else if !visible.is_empty() {
+        let lines: Vec<Line> = visible.iter().flat_map(|entry| entry.to_lines()).collect();
+        Paragraph::new(Text::from(lines))
             .alignment(Alignment::Left)
-            .style(Style::default().fg(Color::White))
+            .scroll((app.log_scroll_offset, 0))
+    } else if app.logs.is_empty() {
+        Paragraph::new("Press 's' to start streaming logs")
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Gray))
     } else {
-        Paragraph::new("Press 'L' to load logs")
+        Paragraph::new("No log lines match the active filter")
             .alignment(Alignment::Center)
             .style(Style::default().fg(Color::Gray))
     };
 
-    let block = Block::default().title("Log").borders(Borders::ALL);
-    f.render_widget(block, area);
-    f.render_widget(content, area);
+    let block = Block::default().title(title).borders(Borders::ALL);
+    f.render_widget(block, log_area);
+    f.render_widget(content, log_area);
+
+    if let Some(search_area) = search_area {
+        let input = Paragraph::new(format!("/{}", app.search_query))
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(input, search_area);
+    }
 }
 
-fn render_settings_page(f: &mut Frame, area: Rect) {
-    let content = Paragraph::new("Settings Page")
-        .alignment(Alignment::Center)
-        .block(Block::default().title("Settings").borders(Borders::ALL));
+fn render_settings_page(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let block = Block::default().title("Settings").borders(Borders::ALL);
+    f.render_widget(block, area);
+
+    let lines: Vec<Line> = if app.settings_editing {
+        let focus = |field: SettingsField| -> Style {
+            if app.settings_focus == field {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+        vec![
+            Line::from(
+                "Editing controller settings ('tab' switch field, 'enter' save, 'esc' cancel)",
+            ),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("Address: {}", app.settings_draft_address),
+                focus(SettingsField::Address),
+            )),
+            Line::from(Span::styled(
+                format!("Secret:  {}", app.settings_draft_secret),
+                focus(SettingsField::Secret),
+            )),
+        ]
+    } else {
+        vec![
+            Line::from(format!("Address: {}", app.controller.base_url)),
+            Line::from(format!("Secret:  {}", app.controller.secret)),
+            Line::from(format!("Config file: {}", app.settings_path.display())),
+            Line::from(""),
+            Line::from("'e' edit, 'r' reload config file"),
+        ]
+    };
+
+    let content = Paragraph::new(Text::from(lines)).alignment(Alignment::Left);
     f.render_widget(content, area);
 }
 
@@ -295,33 +689,48 @@ fn render_config_page(f: &mut Frame, area: Rect, app: &mut AppState) {
     };
 
     f.render_widget(block, area);
+    f.render_widget(content, area);
 }
 
-impl AppState {
-    fn total_lines(&self) -> u16 {
-        if let Some(ref configs) = self.configs {
-            let text = serde_json::to_string_pretty(configs).unwrap_or_default();
-            text.lines().count() as u16
-        } else {
-            0
-        }
+fn diagnostics_line_color(line: &str) -> Color {
+    if line.contains("ERROR") {
+        Color::Red
+    } else if line.contains("WARN") {
+        Color::Yellow
+    } else if line.contains("INFO") {
+        Color::Green
+    } else if line.contains("DEBUG") || line.contains("TRACE") {
+        Color::Gray
+    } else {
+        Color::White
     }
 }
 
+/// Renders the tail of the in-app diagnostics buffer, auto-scrolled to the
+/// newest events.
 fn render_stdout_block(f: &mut Frame, area: Rect, app: &mut AppState) {
-    let content = if app.stdout_output.is_empty() {
-        Paragraph::new("No output")
+    let diagnostics = app.diagnostics.snapshot();
+    let visible_height = area.height.saturating_sub(2).max(1) as usize;
+    let start = diagnostics.len().saturating_sub(visible_height);
+
+    let content = if diagnostics.is_empty() {
+        Paragraph::new("No diagnostics yet")
             .alignment(Alignment::Left)
             .style(Style::default().fg(Color::Gray))
     } else {
-        Paragraph::new(app.stdout_output.clone())
-            .alignment(Alignment::Left)
-            .style(Style::default().fg(Color::Green))
+        let lines: Vec<Line> = diagnostics[start..]
+            .iter()
+            .map(|line| {
+                Line::from(Span::styled(
+                    line.clone(),
+                    Style::default().fg(diagnostics_line_color(line)),
+                ))
+            })
+            .collect();
+        Paragraph::new(Text::from(lines)).alignment(Alignment::Left)
     };
 
-    let block = Block::default()
-        .title("Standard Output")
-        .borders(Borders::ALL);
+    let block = Block::default().title("Diagnostics").borders(Borders::ALL);
 
     f.render_widget(block, area);
     f.render_widget(content, area);
@@ -348,7 +757,7 @@ pub fn render_main_content(f: &mut Frame, area: Rect, app: &mut AppState) {
     match app.current_page {
         AppPage::Proxy => render_proxy_page(f, area),
         AppPage::Log => render_log_page(f, area, app),
-        AppPage::Settings => render_settings_page(f, area),
+        AppPage::Settings => render_settings_page(f, area, app),
         AppPage::Config => render_config_page(f, area, app),
     }
 }
@@ -371,3 +780,70 @@ pub fn render_ui(f: &mut Frame, app: &mut AppState) {
     render_stdout_block(f, stdout_area, app);
     render_bottom_nav_bar(f, nav_area, &app.current_page);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: &str, payload: &str) -> LogEntry {
+        LogEntry {
+            level: level.to_string(),
+            payload: payload.to_string(),
+        }
+    }
+
+    fn test_app() -> AppState {
+        AppState::new(crate::diagnostics::DiagnosticsBuffer::new())
+    }
+
+    #[test]
+    fn test_visible_logs_no_filter_returns_everything() {
+        let mut app = test_app();
+        app.logs.push_back(entry("info", "starting up"));
+        app.logs.push_back(entry("error", "boom"));
+
+        assert_eq!(app.visible_logs().len(), 2);
+    }
+
+    #[test]
+    fn test_visible_logs_min_level_excludes_lower_levels() {
+        let mut app = test_app();
+        app.logs.push_back(entry("debug", "chatter"));
+        app.logs.push_back(entry("warning", "careful"));
+        app.logs.push_back(entry("error", "boom"));
+        app.min_level = Some(LogLevel::Warning);
+
+        let visible: Vec<&str> = app
+            .visible_logs()
+            .into_iter()
+            .map(|e| e.payload.as_str())
+            .collect();
+        assert_eq!(visible, vec!["careful", "boom"]);
+    }
+
+    #[test]
+    fn test_visible_logs_min_level_keeps_unrecognized_levels() {
+        // An entry whose level string isn't one of the four known levels
+        // shouldn't be silently hidden by a min-level filter.
+        let mut app = test_app();
+        app.logs.push_back(entry("trace", "unrecognized level"));
+        app.min_level = Some(LogLevel::Error);
+
+        assert_eq!(app.visible_logs().len(), 1);
+    }
+
+    #[test]
+    fn test_visible_logs_search_query_filters_by_payload_case_insensitively() {
+        let mut app = test_app();
+        app.logs.push_back(entry("info", "Connecting to proxy"));
+        app.logs.push_back(entry("info", "Tunnel established"));
+        app.search_query = "proxy".to_string();
+
+        let visible: Vec<&str> = app
+            .visible_logs()
+            .into_iter()
+            .map(|e| e.payload.as_str())
+            .collect();
+        assert_eq!(visible, vec!["Connecting to proxy"]);
+    }
+}